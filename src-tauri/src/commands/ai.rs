@@ -1,5 +1,6 @@
 use tauri::State;
 use crate::state::AppState;
+use crate::managers::job_manager::JobPayload;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,14 +22,62 @@ pub struct ScreenshotAnalysis {
     pub suggestions: Vec<String>,
 }
 
+/// Dimensionality of the placeholder embedding below. Must match whatever
+/// embedder produced the embeddings passed to `add_document`.
+const EMBEDDING_DIM: usize = 32;
+/// Number of retrieved document chunks to fold into the context when the
+/// caller doesn't supply one.
+const RAG_TOP_K: usize = 3;
+
+/// Placeholder text embedding used until a real embedding model is wired in.
+/// Deterministic so retrieval has something stable to compare against.
+fn embed_placeholder(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f32; EMBEDDING_DIM];
+    for (i, byte) in text.bytes().enumerate() {
+        vector[i % EMBEDDING_DIM] += byte as f32;
+    }
+    vector
+}
+
 /// Query the local LLM (placeholder for future implementation)
 #[tauri::command]
 pub async fn query_llm(
-    query: LLMQuery,
-    _state: State<'_, AppState>,
+    mut query: LLMQuery,
+    state: State<'_, AppState>,
 ) -> Result<LLMResponse, String> {
     tracing::info!("Querying LLM with prompt: {}", query.prompt);
-    
+
+    if query.context.is_none() {
+        let storage_manager = state.storage_manager.read().await;
+        let embedding = embed_placeholder(&query.prompt);
+        // Retrieval is a best-effort enrichment, not a precondition for the
+        // query: a failure here (e.g. a dimension mismatch against documents
+        // inserted by a different embedder) just means no context, not a
+        // failed query.
+        match storage_manager.search_documents(&embedding, RAG_TOP_K).await {
+            Ok(matches) if !matches.is_empty() => {
+                query.context = Some(
+                    matches
+                        .into_iter()
+                        .map(|doc| doc.content)
+                        .collect::<Vec<_>>()
+                        .join("\n\n"),
+                );
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to retrieve RAG context, continuing without it: {}", e),
+        }
+    }
+
+    let job_manager = state.job_manager.read().await;
+    job_manager
+        .enqueue(JobPayload::QueryLlm {
+            prompt: query.prompt.clone(),
+            context: query.context.clone(),
+        })
+        .await
+        .map_err(|e| format!("Failed to run LLM job: {}", e))?;
+
     // TODO: Implement actual LLM integration with llama.cpp or Ollama
     // For now, return a placeholder response
     Ok(LLMResponse {
@@ -40,10 +89,16 @@ pub async fn query_llm(
 /// Capture screen (placeholder for future implementation)
 #[tauri::command]
 pub async fn capture_screen(
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> Result<Vec<u8>, String> {
     tracing::info!("Capturing screen");
-    
+
+    let job_manager = state.job_manager.read().await;
+    job_manager
+        .enqueue(JobPayload::CaptureScreen)
+        .await
+        .map_err(|e| format!("Failed to run capture job: {}", e))?;
+
     // TODO: Implement actual screen capture functionality
     // For now, return empty vec
     Ok(Vec::new())
@@ -53,10 +108,16 @@ pub async fn capture_screen(
 #[tauri::command]
 pub async fn analyze_screenshot(
     image_data: Vec<u8>,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> Result<ScreenshotAnalysis, String> {
     tracing::info!("Analyzing screenshot ({} bytes)", image_data.len());
-    
+
+    let job_manager = state.job_manager.read().await;
+    job_manager
+        .enqueue(JobPayload::AnalyzeScreenshot { image_data })
+        .await
+        .map_err(|e| format!("Failed to run analysis job: {}", e))?;
+
     // TODO: Implement actual vision model analysis
     // For now, return a placeholder response
     Ok(ScreenshotAnalysis {
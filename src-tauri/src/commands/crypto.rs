@@ -1,13 +1,45 @@
 use tauri::State;
 use crate::state::AppState;
-use aes_gcm::{
-    aead::{Aead, KeyInit, OsRng},
-    Aes256Gcm, Nonce,
-};
+use crate::managers::crypto_store;
+use argon2::Argon2;
 use base64::{engine::general_purpose, Engine as _};
+use keyring::Entry;
+use rand::rngs::OsRng;
 use rand::RngCore;
 
-/// Encrypt data using AES-256-GCM
+/// Setting key under which the Argon2id salt for the master key is persisted.
+const MASTER_KEY_SALT_SETTING: &str = "master_key_salt";
+const KEYRING_SERVICE: &str = "com.trayme.desktop";
+const KEYRING_ACCOUNT: &str = "master_key";
+
+fn keyring_entry() -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT).map_err(|e| format!("Failed to access OS keyring: {}", e))
+}
+
+fn derive_key_bytes(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Decode a base64 key and check it's the 32 bytes AES-256-GCM needs.
+fn decode_aes_key(key: &str) -> Result<[u8; 32], String> {
+    let key_bytes = general_purpose::STANDARD
+        .decode(key.as_bytes())
+        .map_err(|e| format!("Failed to decode key: {}", e))?;
+
+    key_bytes
+        .try_into()
+        .map_err(|_| "Key must be 32 bytes (256 bits)".to_string())
+}
+
+/// Encrypt data using AES-256-GCM. Shares its core cipher logic with
+/// `managers::crypto_store`, which wraps the same primitive with zstd
+/// compression and a format header for workspace storage; this command
+/// instead returns the raw `base64(nonce || ciphertext)` a caller-supplied
+/// key round-trips through directly, with no compression or framing.
 #[tauri::command]
 pub async fn encrypt_data(
     data: String,
@@ -15,38 +47,14 @@ pub async fn encrypt_data(
     _state: State<'_, AppState>,
 ) -> Result<String, String> {
     tracing::info!("Encrypting data ({} bytes)", data.len());
-    
-    // Decode the key from base64
-    let key_bytes = general_purpose::STANDARD
-        .decode(key.as_bytes())
-        .map_err(|e| format!("Failed to decode key: {}", e))?;
-    
-    if key_bytes.len() != 32 {
-        return Err("Key must be 32 bytes (256 bits)".to_string());
-    }
-    
-    let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-        .map_err(|e| format!("Failed to create cipher: {}", e))?;
-    
-    // Generate a random nonce
-    let mut nonce_bytes = [0u8; 12];
-    OsRng.fill_bytes(&mut nonce_bytes);
-    let nonce = Nonce::from_slice(&nonce_bytes);
-    
-    // Encrypt the data
-    let ciphertext = cipher
-        .encrypt(nonce, data.as_bytes())
-        .map_err(|e| format!("Encryption failed: {}", e))?;
-    
-    // Combine nonce and ciphertext
-    let mut result = nonce_bytes.to_vec();
-    result.extend_from_slice(&ciphertext);
-    
-    // Encode to base64
-    Ok(general_purpose::STANDARD.encode(result))
+
+    let key_bytes = decode_aes_key(&key)?;
+    let blob = crypto_store::encrypt_bytes(data.as_bytes(), &key_bytes).map_err(|e| e.to_string())?;
+
+    Ok(general_purpose::STANDARD.encode(blob))
 }
 
-/// Decrypt data using AES-256-GCM
+/// Decrypt data using AES-256-GCM. Reverse of [`encrypt_data`].
 #[tauri::command]
 pub async fn decrypt_data(
     encrypted_data: String,
@@ -54,37 +62,142 @@ pub async fn decrypt_data(
     _state: State<'_, AppState>,
 ) -> Result<String, String> {
     tracing::info!("Decrypting data");
-    
-    // Decode the encrypted data from base64
+
     let encrypted_bytes = general_purpose::STANDARD
         .decode(encrypted_data.as_bytes())
         .map_err(|e| format!("Failed to decode encrypted data: {}", e))?;
-    
-    if encrypted_bytes.len() < 12 {
-        return Err("Invalid encrypted data".to_string());
-    }
-    
-    // Decode the key from base64
+    let key_bytes = decode_aes_key(&key)?;
+
+    let plaintext = crypto_store::decrypt_bytes(&encrypted_bytes, &key_bytes).map_err(|e| e.to_string())?;
+
+    String::from_utf8(plaintext).map_err(|e| format!("Failed to convert decrypted data to string: {}", e))
+}
+
+/// Derive a 32-byte master key from a human passphrase using Argon2id. If no
+/// `salt` is given, reuses the per-user salt already persisted in `settings`,
+/// generating and persisting a fresh random one the first time.
+#[tauri::command]
+pub async fn derive_key(
+    passphrase: String,
+    salt: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    tracing::info!("Deriving master key from passphrase");
+
+    let storage_manager = state.storage_manager.read().await;
+
+    let salt_b64 = match salt {
+        Some(salt) => salt,
+        None => match storage_manager
+            .get_setting(MASTER_KEY_SALT_SETTING)
+            .await
+            .map_err(|e| format!("Failed to read master key salt: {}", e))?
+        {
+            Some(existing) => existing,
+            None => {
+                let mut salt_bytes = [0u8; 16];
+                OsRng.fill_bytes(&mut salt_bytes);
+                let generated = general_purpose::STANDARD.encode(salt_bytes);
+                storage_manager
+                    .set_setting(MASTER_KEY_SALT_SETTING, &generated)
+                    .await
+                    .map_err(|e| format!("Failed to persist master key salt: {}", e))?;
+                generated
+            }
+        },
+    };
+
+    let salt_bytes = general_purpose::STANDARD
+        .decode(&salt_b64)
+        .map_err(|e| format!("Failed to decode salt: {}", e))?;
+
+    let key = derive_key_bytes(&passphrase, &salt_bytes)?;
+    Ok(general_purpose::STANDARD.encode(key))
+}
+
+/// Store a derived master key (base64, 32 bytes) in the OS secret store so it
+/// no longer needs to round-trip through the frontend on every unlock.
+#[tauri::command]
+pub async fn store_master_key(key: String, _state: State<'_, AppState>) -> Result<(), String> {
+    tracing::info!("Storing master key in OS keyring");
+
+    keyring_entry()?
+        .set_password(&key)
+        .map_err(|e| format!("Failed to store master key: {}", e))
+}
+
+/// Load the master key from the OS keyring into memory, unlocking encrypted
+/// data for the rest of the session. Returns `false` if no key has been
+/// stored yet.
+#[tauri::command]
+pub async fn unlock(state: State<'_, AppState>) -> Result<bool, String> {
+    tracing::info!("Unlocking master key from OS keyring");
+
+    let key_b64 = match keyring_entry()?.get_password() {
+        Ok(key_b64) => key_b64,
+        Err(keyring::Error::NoEntry) => return Ok(false),
+        Err(e) => return Err(format!("Failed to read master key: {}", e)),
+    };
+
     let key_bytes = general_purpose::STANDARD
-        .decode(key.as_bytes())
-        .map_err(|e| format!("Failed to decode key: {}", e))?;
-    
-    if key_bytes.len() != 32 {
-        return Err("Key must be 32 bytes (256 bits)".to_string());
+        .decode(&key_b64)
+        .map_err(|e| format!("Failed to decode stored master key: {}", e))?;
+    let key: [u8; 32] = key_bytes
+        .try_into()
+        .map_err(|_| "Stored master key is not 32 bytes".to_string())?;
+
+    *state.master_key.write().await = Some(key);
+    Ok(true)
+}
+
+/// Re-derive the master key under a new passphrase and store it in the OS
+/// keyring, mirroring a passphrase-reset flow. Requires the app to already be
+/// unlocked with the old passphrase.
+#[tauri::command]
+pub async fn change_passphrase(
+    old_passphrase: String,
+    new_passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    tracing::info!("Changing master passphrase");
+
+    let storage_manager = state.storage_manager.read().await;
+    let old_salt_b64 = storage_manager
+        .get_setting(MASTER_KEY_SALT_SETTING)
+        .await
+        .map_err(|e| format!("Failed to read master key salt: {}", e))?
+        .ok_or_else(|| "No passphrase has been set yet".to_string())?;
+    let old_salt = general_purpose::STANDARD
+        .decode(&old_salt_b64)
+        .map_err(|e| format!("Failed to decode salt: {}", e))?;
+
+    let old_key = derive_key_bytes(&old_passphrase, &old_salt)?;
+    if *state.master_key.read().await != Some(old_key) {
+        return Err("Old passphrase does not match the unlocked master key".to_string());
     }
-    
-    let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-        .map_err(|e| format!("Failed to create cipher: {}", e))?;
-    
-    // Extract nonce and ciphertext
-    let (nonce_bytes, ciphertext) = encrypted_bytes.split_at(12);
-    let nonce = Nonce::from_slice(nonce_bytes);
-    
-    // Decrypt the data
-    let plaintext = cipher
-        .decrypt(nonce, ciphertext)
-        .map_err(|e| format!("Decryption failed: {}", e))?;
-    
-    String::from_utf8(plaintext)
-        .map_err(|e| format!("Failed to convert decrypted data to string: {}", e))
+
+    let mut new_salt = [0u8; 16];
+    OsRng.fill_bytes(&mut new_salt);
+    let new_key = derive_key_bytes(&new_passphrase, &new_salt)?;
+
+    // Re-encrypt every stored workspace under the new key *before* the old
+    // salt is overwritten or the old keyring entry replaced. If this fails
+    // partway, `old_key` is still derivable from the still-current salt, so
+    // nothing is left permanently unreadable.
+    storage_manager
+        .reencrypt_workspaces(Some(&old_key), &new_key)
+        .await
+        .map_err(|e| format!("Failed to re-encrypt stored workspaces: {}", e))?;
+
+    storage_manager
+        .set_setting(MASTER_KEY_SALT_SETTING, &general_purpose::STANDARD.encode(new_salt))
+        .await
+        .map_err(|e| format!("Failed to persist new master key salt: {}", e))?;
+
+    keyring_entry()?
+        .set_password(&general_purpose::STANDARD.encode(new_key))
+        .map_err(|e| format!("Failed to store new master key: {}", e))?;
+
+    *state.master_key.write().await = Some(new_key);
+    Ok(())
 }
@@ -0,0 +1,45 @@
+use tauri::State;
+use uuid::Uuid;
+use crate::state::AppState;
+use crate::managers::storage_manager::DocumentMatch;
+use crate::utils::errors::command_error;
+
+/// Add a document with its embedding to the RAG document store.
+#[tauri::command]
+pub async fn add_document(
+    title: String,
+    content: String,
+    embedding: Vec<f32>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    tracing::info!("Adding document: {}", title);
+
+    let id = Uuid::new_v4().to_string();
+    let storage_manager = state.storage_manager.read().await;
+    storage_manager
+        .insert_document(&id, &title, &content, &embedding)
+        .await
+        .map_err(command_error)?;
+
+    Ok(id)
+}
+
+/// Retrieve the `k` documents most similar to `embedding` by cosine similarity.
+/// A dimension mismatch against stored embeddings comes back as the
+/// `invalid_input: ...`-prefixed [`AppError::InvalidInput`] variant (see
+/// [`command_error`]) so the frontend can tell it apart from an internal
+/// failure rather than just seeing "search failed".
+#[tauri::command]
+pub async fn search_documents(
+    embedding: Vec<f32>,
+    k: usize,
+    state: State<'_, AppState>,
+) -> Result<Vec<DocumentMatch>, String> {
+    tracing::info!("Searching documents for top {} matches", k);
+
+    let storage_manager = state.storage_manager.read().await;
+    storage_manager
+        .search_documents(&embedding, k)
+        .await
+        .map_err(command_error)
+}
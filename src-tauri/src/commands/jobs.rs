@@ -0,0 +1,39 @@
+use tauri::State;
+use crate::state::AppState;
+use crate::managers::job_manager::JobSummary;
+
+/// List all known AI jobs, most recently updated first.
+#[tauri::command]
+pub async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<JobSummary>, String> {
+    tracing::info!("Listing jobs");
+
+    let job_manager = state.job_manager.read().await;
+    job_manager
+        .list_jobs()
+        .await
+        .map_err(|e| format!("Failed to list jobs: {}", e))
+}
+
+/// Pause a running job, leaving it at its last completed step.
+#[tauri::command]
+pub async fn pause_job(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    tracing::info!("Pausing job: {}", id);
+
+    let job_manager = state.job_manager.read().await;
+    job_manager
+        .pause_job(&id)
+        .await
+        .map_err(|e| format!("Failed to pause job: {}", e))
+}
+
+/// Resume a paused job from its last completed step.
+#[tauri::command]
+pub async fn resume_job(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    tracing::info!("Resuming job: {}", id);
+
+    let job_manager = state.job_manager.read().await;
+    job_manager
+        .resume_job(&id)
+        .await
+        .map_err(|e| format!("Failed to resume job: {}", e))
+}
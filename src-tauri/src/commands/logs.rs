@@ -0,0 +1,21 @@
+use std::str::FromStr;
+use tauri::State;
+use tracing::Level;
+use crate::state::AppState;
+use crate::utils::log_buffer::LogEntry;
+
+/// Return the most recent buffered log entries, most recent first, optionally
+/// filtered to `min_level` and more severe (e.g. `"warn"` returns warnings and
+/// errors only).
+#[tauri::command]
+pub async fn get_recent_logs(
+    limit: usize,
+    min_level: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LogEntry>, String> {
+    let min_level = min_level
+        .map(|level| Level::from_str(&level).map_err(|e| format!("Invalid log level: {}", e)))
+        .transpose()?;
+
+    Ok(state.log_buffer.recent(limit, min_level))
+}
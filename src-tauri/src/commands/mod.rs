@@ -0,0 +1,7 @@
+pub mod ai;
+pub mod crypto;
+pub mod documents;
+pub mod jobs;
+pub mod logs;
+pub mod storage;
+pub mod window;
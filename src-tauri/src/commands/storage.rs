@@ -16,11 +16,8 @@ pub async fn initialize_database(
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     tracing::info!("Initializing database");
-    
-    let storage_manager = state.storage_manager.read().await;
-    let db_path = storage_manager.db_path().display().to_string();
-    
-    Ok(db_path)
+
+    Ok(state.db_path.clone())
 }
 
 /// Save a workspace
@@ -33,13 +30,14 @@ pub async fn save_workspace(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     tracing::info!("Saving workspace: {} ({})", id, name);
-    
+
     let storage_manager = state.storage_manager.read().await;
+    let master_key = state.master_key.read().await;
     storage_manager
-        .save_workspace(&id, &name, description.as_deref(), &windows_data)
+        .save_workspace(&id, &name, description.as_deref(), &windows_data, master_key.as_ref())
         .await
         .map_err(|e| format!("Failed to save workspace: {}", e))?;
-    
+
     Ok(())
 }
 
@@ -50,10 +48,11 @@ pub async fn load_workspace(
     state: State<'_, AppState>,
 ) -> Result<Option<WorkspaceInfo>, String> {
     tracing::info!("Loading workspace: {}", id);
-    
+
     let storage_manager = state.storage_manager.read().await;
+    let master_key = state.master_key.read().await;
     let workspace = storage_manager
-        .load_workspace(&id)
+        .load_workspace(&id, master_key.as_ref())
         .await
         .map_err(|e| format!("Failed to load workspace: {}", e))?;
     
@@ -7,11 +7,14 @@ mod models;
 mod state;
 mod utils;
 
-use state::AppState;
+use state::{AppState, LOG_BUFFER_CAPACITY};
 use tauri::Manager;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use utils::log_buffer::{LogBuffer, LogBufferLayer};
 
 fn main() {
+    let log_buffer = LogBuffer::new(LOG_BUFFER_CAPACITY);
+
     // Initialize logging
     tracing_subscriber::registry()
         .with(
@@ -19,19 +22,30 @@ fn main() {
                 .unwrap_or_else(|_| "trayme_desktop_os=debug,tauri=info".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        .with(LogBufferLayer::new(log_buffer.clone()))
         .init();
 
     tracing::info!("Starting TrayMe Desktop OS");
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .setup(|app| {
+        .setup(move |app| {
             tracing::info!("Setting up application");
-            
+
             // Initialize app state
-            let state = AppState::new(app.handle().clone())?;
+            let state = AppState::new(app.handle().clone(), log_buffer.clone())?;
             app.manage(state);
 
+            // Resume any AI jobs left running/paused by a previous session
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                let job_manager = state.job_manager.read().await;
+                if let Err(err) = job_manager.resume_incomplete_jobs().await {
+                    tracing::error!("Failed to resume incomplete jobs: {}", err);
+                }
+            });
+
             // Set up system tray
             setup_system_tray(app)?;
 
@@ -50,6 +64,16 @@ fn main() {
             commands::ai::analyze_screenshot,
             commands::crypto::encrypt_data,
             commands::crypto::decrypt_data,
+            commands::crypto::derive_key,
+            commands::crypto::store_master_key,
+            commands::crypto::unlock,
+            commands::crypto::change_passphrase,
+            commands::jobs::list_jobs,
+            commands::jobs::pause_job,
+            commands::jobs::resume_job,
+            commands::logs::get_recent_logs,
+            commands::documents::add_document,
+            commands::documents::search_documents,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,85 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use rand::RngCore;
+
+/// Row holds plaintext text, not one of our sealed formats. Legacy rows from
+/// before this feature existed look like this.
+const FORMAT_PLAINTEXT: u8 = 0;
+/// Row holds zstd-compressed data sealed with AES-256-GCM.
+const FORMAT_ZSTD_AES256GCM: u8 = 1;
+
+/// Core AES-256-GCM primitive: encrypt `plaintext` under `key` with a fresh
+/// random nonce, returning `nonce || ciphertext`. This is the one place that
+/// touches the cipher directly; [`seal`] and `commands::crypto`'s
+/// `encrypt_data` both build on it instead of each rolling their own.
+pub fn encrypt_bytes(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new_from_slice(key).context("Failed to create cipher")?;
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext)
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    let mut blob = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
+    blob.extend_from_slice(&nonce_bytes);
+    blob.extend_from_slice(&ciphertext);
+    Ok(blob)
+}
+
+/// Reverse of [`encrypt_bytes`]: split the leading 12-byte nonce off `blob`
+/// and decrypt the rest under `key`.
+pub fn decrypt_bytes(blob: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
+    if blob.len() < 12 {
+        anyhow::bail!("Encrypted blob is too short to contain a nonce");
+    }
+    let (nonce_bytes, ciphertext) = blob.split_at(12);
+
+    let cipher = Aes256Gcm::new_from_slice(key).context("Failed to create cipher")?;
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))
+}
+
+/// Compress `plaintext` with zstd and seal it with AES-256-GCM under `key`,
+/// returning a base64 string safe to store in a `TEXT` column. The leading
+/// format byte lets [`open`] tell sealed rows apart from legacy plaintext rows
+/// so the rollout can be incremental instead of a single big migration.
+pub fn seal(plaintext: &[u8], key: &[u8; 32]) -> Result<String> {
+    let compressed = zstd::stream::encode_all(plaintext, 0).context("Failed to compress data")?;
+    let encrypted = encrypt_bytes(&compressed, key)?;
+
+    let mut blob = Vec::with_capacity(1 + encrypted.len());
+    blob.push(FORMAT_ZSTD_AES256GCM);
+    blob.extend_from_slice(&encrypted);
+
+    Ok(general_purpose::STANDARD.encode(blob))
+}
+
+/// Reverse of [`seal`]. Rows that aren't valid base64, or whose decoded format
+/// byte isn't one we recognize, are assumed to be pre-existing plaintext and
+/// returned unchanged.
+pub fn open(stored: &str, key: Option<&[u8; 32]>) -> Result<String> {
+    let Ok(blob) = general_purpose::STANDARD.decode(stored) else {
+        return Ok(stored.to_string());
+    };
+
+    match blob.first().copied() {
+        Some(FORMAT_ZSTD_AES256GCM) => {
+            let key = key.context("Data is encrypted but no master key is unlocked")?;
+            let compressed = decrypt_bytes(&blob[1..], key)?;
+            let plaintext = zstd::stream::decode_all(compressed.as_slice()).context("Failed to decompress data")?;
+
+            String::from_utf8(plaintext).context("Decrypted data was not valid UTF-8")
+        }
+        Some(FORMAT_PLAINTEXT) => Ok(stored.to_string()),
+        _ => Ok(stored.to_string()),
+    }
+}
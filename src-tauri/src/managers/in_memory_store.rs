@@ -0,0 +1,199 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use crate::managers::crypto_store;
+use crate::managers::storage_manager::{DocumentMatch, WorkspaceData};
+use crate::managers::workspace_store::{cosine_similarity, WorkspaceStore};
+use crate::utils::errors::AppError;
+
+struct StoredDocument {
+    title: String,
+    content: String,
+    embedding: Vec<f32>,
+}
+
+/// A `WorkspaceStore` backed by plain `HashMap`s instead of a database. Meant
+/// for unit tests and other callers that want the storage contract without
+/// the overhead (or filesystem/SQLite footprint) of a real pool.
+pub struct InMemoryStore {
+    workspaces: RwLock<HashMap<String, WorkspaceData>>,
+    documents: RwLock<HashMap<String, StoredDocument>>,
+    settings: RwLock<HashMap<String, String>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self {
+            workspaces: RwLock::new(HashMap::new()),
+            documents: RwLock::new(HashMap::new()),
+            settings: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WorkspaceStore for InMemoryStore {
+    async fn save_workspace(
+        &self,
+        id: &str,
+        name: &str,
+        description: Option<&str>,
+        windows_data: &str,
+        master_key: Option<&[u8; 32]>,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let stored_windows_data = match master_key {
+            Some(key) => crypto_store::seal(windows_data.as_bytes(), key)?,
+            None => windows_data.to_string(),
+        };
+
+        let mut workspaces = self.workspaces.write().await;
+        let created_at = workspaces.get(id).map(|w| w.created_at).unwrap_or(now);
+
+        workspaces.insert(
+            id.to_string(),
+            WorkspaceData {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: description.map(str::to_string),
+                windows_data: stored_windows_data,
+                created_at,
+                updated_at: now,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn load_workspace(&self, id: &str, master_key: Option<&[u8; 32]>) -> Result<Option<WorkspaceData>> {
+        let workspaces = self.workspaces.read().await;
+        workspaces
+            .get(id)
+            .map(|w| decrypt_workspace(w, master_key))
+            .transpose()
+    }
+
+    async fn list_workspaces(&self, master_key: Option<&[u8; 32]>) -> Result<Vec<WorkspaceData>> {
+        let workspaces = self.workspaces.read().await;
+        let mut result: Vec<WorkspaceData> = workspaces
+            .values()
+            .map(|w| decrypt_workspace(w, master_key))
+            .collect::<Result<_>>()?;
+        result.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(result)
+    }
+
+    async fn reencrypt_workspaces(&self, old_key: Option<&[u8; 32]>, new_key: &[u8; 32]) -> Result<()> {
+        let mut workspaces = self.workspaces.write().await;
+        // Re-seal everything before writing any of it back, so a failure
+        // leaves every workspace under its original key.
+        let mut resealed = Vec::with_capacity(workspaces.len());
+        for (id, workspace) in workspaces.iter() {
+            let plaintext = crypto_store::open(&workspace.windows_data, old_key)?;
+            resealed.push((id.clone(), crypto_store::seal(plaintext.as_bytes(), new_key)?));
+        }
+        for (id, windows_data) in resealed {
+            if let Some(workspace) = workspaces.get_mut(&id) {
+                workspace.windows_data = windows_data;
+            }
+        }
+        Ok(())
+    }
+
+    async fn insert_document(&self, id: &str, title: &str, content: &str, embedding: &[f32]) -> Result<()> {
+        self.documents.write().await.insert(
+            id.to_string(),
+            StoredDocument {
+                title: title.to_string(),
+                content: content.to_string(),
+                embedding: embedding.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn search_documents(&self, query: &[f32], k: usize) -> Result<Vec<DocumentMatch>> {
+        let documents = self.documents.read().await;
+
+        let mut scored = Vec::with_capacity(documents.len());
+        for (id, doc) in documents.iter() {
+            if doc.embedding.len() != query.len() {
+                return Err(AppError::InvalidInput(format!(
+                    "document {} has a {}-dimensional embedding but the query is {}-dimensional",
+                    id,
+                    doc.embedding.len(),
+                    query.len()
+                ))
+                .into());
+            }
+
+            let score = cosine_similarity(query, &doc.embedding);
+            scored.push(DocumentMatch {
+                id: id.clone(),
+                title: doc.title.clone(),
+                content: doc.content.clone(),
+                score,
+            });
+        }
+
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        Ok(scored)
+    }
+
+    async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.settings.read().await.get(key).cloned())
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.settings.write().await.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+fn decrypt_workspace(workspace: &WorkspaceData, master_key: Option<&[u8; 32]>) -> Result<WorkspaceData> {
+    Ok(WorkspaceData {
+        windows_data: crypto_store::open(&workspace.windows_data, master_key)?,
+        ..workspace.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn save_load_and_list_workspaces_round_trip() {
+        let store = InMemoryStore::new();
+
+        store
+            .save_workspace("ws-1", "Workspace One", Some("first"), "{\"windows\":[]}", None)
+            .await
+            .expect("save_workspace should succeed");
+
+        let loaded = store
+            .load_workspace("ws-1", None)
+            .await
+            .expect("load_workspace should succeed")
+            .expect("workspace should exist");
+        assert_eq!(loaded.name, "Workspace One");
+        assert_eq!(loaded.description.as_deref(), Some("first"));
+
+        store
+            .save_workspace("ws-2", "Workspace Two", None, "{\"windows\":[]}", None)
+            .await
+            .expect("save_workspace should succeed");
+
+        let all = store.list_workspaces(None).await.expect("list_workspaces should succeed");
+        assert_eq!(all.len(), 2);
+    }
+}
+
@@ -0,0 +1,301 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use uuid::Uuid;
+
+/// The kind of long-running AI work a job performs, together with whatever
+/// input it needs to resume. Kept as one enum (rather than a table per kind)
+/// so a single `jobs` table and a single resume loop can drive all of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JobPayload {
+    CaptureScreen,
+    AnalyzeScreenshot {
+        // Without this, rmp_serde encodes a bare `Vec<u8>` as a msgpack array
+        // of integers (1-2 bytes per byte) rather than a `bin` blob, which
+        // defeats the point of using MessagePack for compactness here.
+        #[serde(with = "serde_bytes")]
+        image_data: Vec<u8>,
+    },
+    QueryLlm { prompt: String, context: Option<String> },
+}
+
+impl JobPayload {
+    fn type_name(&self) -> &'static str {
+        match self {
+            JobPayload::CaptureScreen => "capture_screen",
+            JobPayload::AnalyzeScreenshot { .. } => "analyze_screenshot",
+            JobPayload::QueryLlm { .. } => "query_llm",
+        }
+    }
+
+    /// Number of resumable steps this job kind is broken into.
+    fn total_steps(&self) -> u32 {
+        match self {
+            JobPayload::CaptureScreen => 1,
+            JobPayload::AnalyzeScreenshot { .. } => 2,
+            JobPayload::QueryLlm { .. } => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Paused,
+    Completed,
+    Failed,
+}
+
+impl JobStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Paused => "paused",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "running" => Ok(JobStatus::Running),
+            "paused" => Ok(JobStatus::Paused),
+            "completed" => Ok(JobStatus::Completed),
+            "failed" => Ok(JobStatus::Failed),
+            other => anyhow::bail!("Unknown job status: {}", other),
+        }
+    }
+}
+
+/// The part of a job's progress that survives a restart: which step it's on
+/// and the payload it was given. Serialized as MessagePack rather than JSON
+/// so binary fields (e.g. screenshot bytes) stay compact in the `state` BLOB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JobState {
+    payload: JobPayload,
+    step: u32,
+}
+
+/// A job as surfaced to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSummary {
+    pub id: String,
+    pub job_type: String,
+    pub status: String,
+    pub step: u32,
+    pub total_steps: u32,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+struct JobRecord {
+    id: String,
+    status: JobStatus,
+    state: JobState,
+}
+
+/// Runs AI jobs (screen capture, screenshot analysis, LLM queries) as a
+/// sequence of resumable steps, persisting progress after every step so work
+/// survives an app restart instead of being lost mid-operation.
+#[derive(Clone)]
+pub struct JobManager {
+    pool: SqlitePool,
+}
+
+impl JobManager {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Enqueue a new job and drive it to completion or pause in the
+    /// background, returning the job id immediately. The caller is a Tauri
+    /// command, so it must not block for the job's full duration once real
+    /// (non-placeholder) step work lands here.
+    pub async fn enqueue(&self, payload: JobPayload) -> Result<String> {
+        let id = Uuid::new_v4().to_string();
+        let state = JobState { payload, step: 0 };
+        let state_bytes = rmp_serde::to_vec(&state).context("Failed to serialize job state")?;
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, job_type, status, step_index, state, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(state.payload.type_name())
+        .bind(JobStatus::Running.as_str())
+        .bind(state.step as i64)
+        .bind(&state_bytes)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert job")?;
+
+        tracing::info!("Enqueued job {} ({})", id, state.payload.type_name());
+
+        self.spawn_run(id.clone());
+        Ok(id)
+    }
+
+    /// Drive a job to completion or pause on a background task, logging
+    /// (rather than propagating) a failure since there's no caller left to
+    /// return it to.
+    fn spawn_run(&self, id: String) {
+        let manager = self.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = manager.run(&id).await {
+                tracing::error!("Job {} failed: {}", id, err);
+            }
+        });
+    }
+
+    /// Drive a job forward from its last completed step until it finishes,
+    /// pauses, or fails. Writing the new step index and state back inside a
+    /// transaction after each step is what makes resuming safe: a crash
+    /// between steps just leaves the last committed step as the resume point.
+    async fn run(&self, id: &str) -> Result<()> {
+        loop {
+            let Some(mut record) = self.load(id).await? else {
+                return Ok(());
+            };
+
+            if record.status != JobStatus::Running {
+                return Ok(());
+            }
+
+            let total_steps = record.state.payload.total_steps();
+            if record.state.step >= total_steps {
+                self.set_status(id, JobStatus::Completed).await?;
+                tracing::info!("Job {} completed", id);
+                return Ok(());
+            }
+
+            tracing::info!(
+                "Running job {} step {}/{}",
+                id,
+                record.state.step + 1,
+                total_steps
+            );
+
+            // TODO: Implement the actual step work (screen capture / vision
+            // model / LLM call). For now each step is a no-op placeholder that
+            // still exercises the persisted, resumable step sequence.
+            record.state.step += 1;
+            self.save_step(&mut record).await?;
+        }
+    }
+
+    async fn load(&self, id: &str) -> Result<Option<JobRecord>> {
+        let row: Option<(String, String, Vec<u8>)> =
+            sqlx::query_as("SELECT id, status, state FROM jobs WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to load job")?;
+
+        row.map(|(id, status, state_bytes)| {
+            Ok(JobRecord {
+                id,
+                status: JobStatus::from_str(&status)?,
+                state: rmp_serde::from_slice(&state_bytes).context("Failed to deserialize job state")?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn save_step(&self, record: &mut JobRecord) -> Result<()> {
+        let state_bytes = rmp_serde::to_vec(&record.state).context("Failed to serialize job state")?;
+        let mut tx = self.pool.begin().await.context("Failed to begin job step transaction")?;
+
+        sqlx::query("UPDATE jobs SET step_index = ?, state = ?, updated_at = ? WHERE id = ?")
+            .bind(record.state.step as i64)
+            .bind(&state_bytes)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(&record.id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to persist job step")?;
+
+        tx.commit().await.context("Failed to commit job step")?;
+        Ok(())
+    }
+
+    async fn set_status(&self, id: &str, status: JobStatus) -> Result<()> {
+        sqlx::query("UPDATE jobs SET status = ?, updated_at = ? WHERE id = ?")
+            .bind(status.as_str())
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update job status")?;
+        Ok(())
+    }
+
+    /// Pause a running job. It will stay at its last completed step until
+    /// [`JobManager::resume_job`] is called or the next startup resumes it
+    /// via [`JobManager::resume_incomplete_jobs`].
+    pub async fn pause_job(&self, id: &str) -> Result<()> {
+        tracing::info!("Pausing job {}", id);
+        self.set_status(id, JobStatus::Paused).await
+    }
+
+    /// Resume a paused job from its last completed step, in the background.
+    pub async fn resume_job(&self, id: &str) -> Result<()> {
+        tracing::info!("Resuming job {}", id);
+        self.set_status(id, JobStatus::Running).await?;
+        self.spawn_run(id.to_string());
+        Ok(())
+    }
+
+    /// List all known jobs, most recently updated first.
+    pub async fn list_jobs(&self) -> Result<Vec<JobSummary>> {
+        let rows: Vec<(String, String, String, i64, Vec<u8>, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT id, job_type, status, step_index, state, created_at, updated_at
+            FROM jobs ORDER BY updated_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list jobs")?;
+
+        rows.into_iter()
+            .map(|(id, job_type, status, step_index, state_bytes, created_at, updated_at)| {
+                let state: JobState =
+                    rmp_serde::from_slice(&state_bytes).context("Failed to deserialize job state")?;
+                Ok(JobSummary {
+                    id,
+                    job_type,
+                    status,
+                    step: step_index as u32,
+                    total_steps: state.payload.total_steps(),
+                    created_at,
+                    updated_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Scan for jobs left `Running` or `Paused` by a previous run (e.g. the
+    /// app was killed mid-job), mark them `Running` again and re-enqueue them
+    /// from their last completed step. Called once from `setup()`.
+    pub async fn resume_incomplete_jobs(&self) -> Result<()> {
+        let ids: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM jobs WHERE status IN ('running', 'paused')")
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to query incomplete jobs")?;
+
+        for id in ids {
+            tracing::info!("Re-enqueuing job {} from its last completed step", id);
+            self.set_status(&id, JobStatus::Running).await?;
+            self.spawn_run(id);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,6 @@
+pub mod crypto_store;
+pub mod in_memory_store;
+pub mod job_manager;
+pub mod storage_manager;
+pub mod window_manager;
+pub mod workspace_store;
@@ -1,9 +1,68 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::path::PathBuf;
 use std::str::FromStr;
 use tauri::{AppHandle, Manager};
 
+use crate::managers::crypto_store;
+use crate::managers::workspace_store::{cosine_similarity, WorkspaceStore};
+use crate::utils::errors::AppError;
+
+/// A single ordered, tracked schema change. Migrations are applied in ascending
+/// `version` order and never rewritten once released.
+struct Migration {
+    version: u32,
+    sql: &'static str,
+}
+
+/// All schema migrations, in order. Adding a new one means appending a new
+/// `Migration` with the next version number, never editing an existing entry.
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    sql: r#"
+        CREATE TABLE IF NOT EXISTS workspaces (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            windows_data TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS documents (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            content TEXT NOT NULL,
+            embedding BLOB,
+            metadata TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+    "#,
+}, Migration {
+    version: 2,
+    sql: r#"
+        CREATE TABLE IF NOT EXISTS jobs (
+            id TEXT PRIMARY KEY,
+            job_type TEXT NOT NULL,
+            status TEXT NOT NULL,
+            step_index INTEGER NOT NULL,
+            state BLOB NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+    "#,
+}];
+
 /// Manages local SQLite database for persistent storage
 pub struct StorageManager {
     pool: SqlitePool,
@@ -17,17 +76,17 @@ impl StorageManager {
             .path()
             .app_data_dir()
             .context("Failed to get app data directory")?;
-        
+
         std::fs::create_dir_all(&app_dir)
             .context("Failed to create app data directory")?;
 
         let db_path = app_dir.join("trayme.db");
-        
+
         tracing::info!("Database path: {:?}", db_path);
 
         // Create a runtime for async operations
         let rt = tokio::runtime::Runtime::new()?;
-        
+
         let pool = rt.block_on(async {
             let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", db_path.display()))?
                 .create_if_missing(true);
@@ -39,66 +98,119 @@ impl StorageManager {
                 .context("Failed to connect to database")
         })?;
 
-        // Initialize database schema
-        rt.block_on(async {
-            Self::initialize_schema(&pool).await
-        })?;
+        // Bring the schema up to the latest migration
+        let (from_version, to_version) = rt.block_on(async { Self::run_migrations(&pool).await })?;
+        if to_version > from_version {
+            tracing::info!(
+                "Migrated database schema from version {} to {}",
+                from_version,
+                to_version
+            );
+        }
 
         Ok(Self { pool, db_path })
     }
 
-    /// Initialize database schema with all required tables
-    async fn initialize_schema(pool: &SqlitePool) -> Result<()> {
-        tracing::info!("Initializing database schema");
+    /// Open an ephemeral, in-memory database instead of one on disk. Used by
+    /// integration tests and by "incognito" sessions whose workspaces should
+    /// never touch the filesystem. `max_connections(1)` is required here: a
+    /// pooled `sqlite::memory:` connection is a fresh, empty database per
+    /// connection, so anything beyond a single connection would silently lose
+    /// data between queries.
+    pub fn new_in_memory() -> Result<Self> {
+        let rt = tokio::runtime::Runtime::new()?;
+        rt.block_on(Self::new_in_memory_async())
+    }
 
-        // Workspaces table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS workspaces (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT,
-                windows_data TEXT NOT NULL,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
-            )
-            "#,
-        )
-        .execute(pool)
-        .await?;
+    /// Async-friendly counterpart to `new_in_memory`. `new_in_memory` spins up
+    /// its own `tokio::runtime::Runtime` and blocks on it, which panics with
+    /// "Cannot start a runtime from within a runtime" when called from inside
+    /// one already (e.g. a `#[tokio::test]`). Callers that are already async
+    /// should use this instead.
+    pub async fn new_in_memory_async() -> Result<Self> {
+        tracing::info!("Opening in-memory database");
 
-        // Settings table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS settings (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL,
-                updated_at INTEGER NOT NULL
-            )
-            "#,
-        )
-        .execute(pool)
-        .await?;
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?.create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .context("Failed to open in-memory database")?;
 
-        // RAG documents table (for future AI integration)
+        Self::run_migrations(&pool).await?;
+
+        Ok(Self {
+            pool,
+            db_path: PathBuf::from(":memory:"),
+        })
+    }
+
+    /// Run every migration with a version higher than the one currently applied,
+    /// each inside its own transaction, recording progress in `schema_migrations`
+    /// as it goes. Returns the `(from, to)` schema version. A failing migration
+    /// aborts its own transaction and returns the error immediately rather than
+    /// continuing on to later migrations, so the database is never left
+    /// half-upgraded.
+    async fn run_migrations(pool: &SqlitePool) -> Result<(u32, u32)> {
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS documents (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                content TEXT NOT NULL,
-                embedding BLOB,
-                metadata TEXT,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                applied_at INTEGER NOT NULL
             )
             "#,
         )
         .execute(pool)
-        .await?;
+        .await
+        .context("Failed to create schema_migrations table")?;
 
-        tracing::info!("Database schema initialized successfully");
-        Ok(())
+        let from_version: i64 =
+            sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_migrations")
+                .fetch_one(pool)
+                .await
+                .context("Failed to read current schema version")?;
+        let from_version = from_version as u32;
+
+        let mut to_version = from_version;
+        for migration in MIGRATIONS.iter().filter(|m| m.version > from_version) {
+            let mut tx = pool
+                .begin()
+                .await
+                .context("Failed to begin migration transaction")?;
+
+            sqlx::raw_sql(migration.sql)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Migration {} failed; database remains at version {}",
+                        migration.version, to_version
+                    )
+                })?;
+
+            sqlx::query("INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)")
+                .bind(migration.version as i64)
+                .bind(chrono::Utc::now().timestamp())
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to record migration {}", migration.version))?;
+
+            tx.commit()
+                .await
+                .with_context(|| format!("Failed to commit migration {}", migration.version))?;
+
+            tracing::info!("Applied schema migration {}", migration.version);
+            to_version = migration.version;
+        }
+
+        Ok((from_version, to_version))
+    }
+
+    /// Migrate the database to the latest schema version, returning the
+    /// `(from, to)` version pair.
+    pub async fn migrate(&self) -> Result<(u32, u32)> {
+        Self::run_migrations(&self.pool).await
     }
 
     /// Get the database connection pool
@@ -111,10 +223,23 @@ impl StorageManager {
         &self.db_path
     }
 
-    /// Save workspace data to database
-    pub async fn save_workspace(&self, id: &str, name: &str, description: Option<&str>, windows_data: &str) -> Result<()> {
+    /// Save workspace data to database. When `master_key` is present,
+    /// `windows_data` is compressed and sealed before it touches disk; when
+    /// absent it's stored as plaintext JSON, same as before this feature.
+    pub async fn save_workspace(
+        &self,
+        id: &str,
+        name: &str,
+        description: Option<&str>,
+        windows_data: &str,
+        master_key: Option<&[u8; 32]>,
+    ) -> Result<()> {
         let now = chrono::Utc::now().timestamp();
-        
+        let stored_windows_data = match master_key {
+            Some(key) => crypto_store::seal(windows_data.as_bytes(), key)?,
+            None => windows_data.to_string(),
+        };
+
         sqlx::query(
             r#"
             INSERT INTO workspaces (id, name, description, windows_data, created_at, updated_at)
@@ -129,7 +254,7 @@ impl StorageManager {
         .bind(id)
         .bind(name)
         .bind(description)
-        .bind(windows_data)
+        .bind(stored_windows_data)
         .bind(now)
         .bind(now)
         .execute(&self.pool)
@@ -138,8 +263,9 @@ impl StorageManager {
         Ok(())
     }
 
-    /// Load workspace data from database
-    pub async fn load_workspace(&self, id: &str) -> Result<Option<WorkspaceData>> {
+    /// Load workspace data from database, transparently decrypting
+    /// `windows_data` if it was sealed with a master key.
+    pub async fn load_workspace(&self, id: &str, master_key: Option<&[u8; 32]>) -> Result<Option<WorkspaceData>> {
         let row = sqlx::query_as::<_, WorkspaceRow>(
             "SELECT id, name, description, windows_data, created_at, updated_at FROM workspaces WHERE id = ?"
         )
@@ -147,32 +273,219 @@ impl StorageManager {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|r| WorkspaceData {
-            id: r.id,
-            name: r.name,
-            description: r.description,
-            windows_data: r.windows_data,
-            created_at: r.created_at,
-            updated_at: r.updated_at,
-        }))
+        row.map(|r| {
+            Ok(WorkspaceData {
+                id: r.id,
+                name: r.name,
+                description: r.description,
+                windows_data: crypto_store::open(&r.windows_data, master_key)?,
+                created_at: r.created_at,
+                updated_at: r.updated_at,
+            })
+        })
+        .transpose()
     }
 
-    /// List all workspaces
-    pub async fn list_workspaces(&self) -> Result<Vec<WorkspaceData>> {
+    /// List all workspaces, transparently decrypting `windows_data` where it
+    /// was sealed with a master key.
+    pub async fn list_workspaces(&self, master_key: Option<&[u8; 32]>) -> Result<Vec<WorkspaceData>> {
         let rows = sqlx::query_as::<_, WorkspaceRow>(
             "SELECT id, name, description, windows_data, created_at, updated_at FROM workspaces ORDER BY updated_at DESC"
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(|r| WorkspaceData {
-            id: r.id,
-            name: r.name,
-            description: r.description,
-            windows_data: r.windows_data,
-            created_at: r.created_at,
-            updated_at: r.updated_at,
-        }).collect())
+        rows.into_iter()
+            .map(|r| {
+                Ok(WorkspaceData {
+                    id: r.id,
+                    name: r.name,
+                    description: r.description,
+                    windows_data: crypto_store::open(&r.windows_data, master_key)?,
+                    created_at: r.created_at,
+                    updated_at: r.updated_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Re-seal every stored workspace's `windows_data` under `new_key`,
+    /// decrypting with `old_key` first where needed. Used by the
+    /// passphrase-change flow so previously-sealed workspaces stay readable
+    /// after the master key rotates. Runs as a single transaction so a
+    /// mid-loop failure leaves every row under its original key rather than
+    /// a mix of old-key and new-key blobs.
+    pub async fn reencrypt_workspaces(&self, old_key: Option<&[u8; 32]>, new_key: &[u8; 32]) -> Result<()> {
+        let rows = sqlx::query_as::<_, WorkspaceRow>(
+            "SELECT id, name, description, windows_data, created_at, updated_at FROM workspaces",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut tx = self.pool.begin().await.context("Failed to begin re-encryption transaction")?;
+
+        for row in rows {
+            let plaintext = crypto_store::open(&row.windows_data, old_key)?;
+            let resealed = crypto_store::seal(plaintext.as_bytes(), new_key)?;
+
+            sqlx::query("UPDATE workspaces SET windows_data = ? WHERE id = ?")
+                .bind(resealed)
+                .bind(&row.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await.context("Failed to commit re-encryption transaction")?;
+
+        Ok(())
+    }
+
+    /// Insert a document with its embedding for later retrieval. The
+    /// embedding is stored as little-endian `f32` bytes in the BLOB column
+    /// rather than JSON, since SQLite has no native vector type.
+    pub async fn insert_document(&self, id: &str, title: &str, content: &str, embedding: &[f32]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO documents (id, title, content, embedding, metadata, created_at, updated_at)
+            VALUES (?, ?, ?, ?, NULL, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title,
+                content = excluded.content,
+                embedding = excluded.embedding,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(title)
+        .bind(content)
+        .bind(encode_embedding(embedding))
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Return the `k` documents whose embeddings are most similar to `query`
+    /// by cosine similarity. SQLite has no vector index, so every candidate
+    /// embedding is loaded and scored in Rust, keeping only the top `k` in a
+    /// bounded min-heap rather than sorting the full candidate set.
+    pub async fn search_documents(&self, query: &[f32], k: usize) -> Result<Vec<DocumentMatch>> {
+        let rows: Vec<(String, String, String, Option<Vec<u8>>)> =
+            sqlx::query_as("SELECT id, title, content, embedding FROM documents")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut heap: BinaryHeap<ScoredDocument> = BinaryHeap::with_capacity(k + 1);
+        for (id, title, content, embedding) in rows {
+            let Some(embedding) = embedding else { continue };
+            let embedding = decode_embedding(&embedding);
+
+            if embedding.len() != query.len() {
+                return Err(AppError::InvalidInput(format!(
+                    "document {} has a {}-dimensional embedding but the query is {}-dimensional",
+                    id,
+                    embedding.len(),
+                    query.len()
+                ))
+                .into());
+            }
+
+            let score = cosine_similarity(query, &embedding);
+            heap.push(ScoredDocument { score, id, title, content });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        let mut results: Vec<DocumentMatch> = heap
+            .into_vec()
+            .into_iter()
+            .map(|doc| DocumentMatch {
+                id: doc.id,
+                title: doc.title,
+                content: doc.content,
+                score: doc.score,
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+
+        Ok(results)
+    }
+
+    /// Read a value from the `settings` key/value table.
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let value: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(value)
+    }
+
+    /// Write (or overwrite) a value in the `settings` key/value table.
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO settings (key, value, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WorkspaceStore for StorageManager {
+    async fn save_workspace(
+        &self,
+        id: &str,
+        name: &str,
+        description: Option<&str>,
+        windows_data: &str,
+        master_key: Option<&[u8; 32]>,
+    ) -> Result<()> {
+        StorageManager::save_workspace(self, id, name, description, windows_data, master_key).await
+    }
+
+    async fn load_workspace(&self, id: &str, master_key: Option<&[u8; 32]>) -> Result<Option<WorkspaceData>> {
+        StorageManager::load_workspace(self, id, master_key).await
+    }
+
+    async fn list_workspaces(&self, master_key: Option<&[u8; 32]>) -> Result<Vec<WorkspaceData>> {
+        StorageManager::list_workspaces(self, master_key).await
+    }
+
+    async fn reencrypt_workspaces(&self, old_key: Option<&[u8; 32]>, new_key: &[u8; 32]) -> Result<()> {
+        StorageManager::reencrypt_workspaces(self, old_key, new_key).await
+    }
+
+    async fn insert_document(&self, id: &str, title: &str, content: &str, embedding: &[f32]) -> Result<()> {
+        StorageManager::insert_document(self, id, title, content, embedding).await
+    }
+
+    async fn search_documents(&self, query: &[f32], k: usize) -> Result<Vec<DocumentMatch>> {
+        StorageManager::search_documents(self, query, k).await
+    }
+
+    async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        StorageManager::get_setting(self, key).await
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        StorageManager::set_setting(self, key, value).await
     }
 }
 
@@ -195,3 +508,100 @@ pub struct WorkspaceData {
     pub created_at: i64,
     pub updated_at: i64,
 }
+
+/// A document returned from [`StorageManager::search_documents`], ranked by
+/// cosine similarity to the query embedding.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DocumentMatch {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub score: f32,
+}
+
+/// A document candidate held in the bounded top-k heap during
+/// [`StorageManager::search_documents`]. Ordered by `score` so the heap root
+/// (the smallest score so far) is what gets evicted once the heap grows past
+/// `k`.
+struct ScoredDocument {
+    score: f32,
+    id: String,
+    title: String,
+    content: String,
+}
+
+impl PartialEq for ScoredDocument {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+
+impl Eq for ScoredDocument {}
+
+impl PartialOrd for ScoredDocument {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredDocument {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) surfaces the *lowest* score at
+        // its root, which is what we want to evict once the heap exceeds `k`.
+        other.score.partial_cmp(&self.score).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Encode an embedding as little-endian `f32` bytes for the `embedding` BLOB column.
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Decode little-endian `f32` bytes back into an embedding.
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().expect("chunks_exact(4) guarantees length 4")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn save_load_and_list_workspaces_round_trip() {
+        let storage = StorageManager::new_in_memory_async()
+            .await
+            .expect("in-memory storage manager should open");
+
+        storage
+            .save_workspace("ws-1", "Workspace One", Some("first"), "{\"windows\":[]}", None)
+            .await
+            .expect("save_workspace should succeed");
+
+        let loaded = storage
+            .load_workspace("ws-1", None)
+            .await
+            .expect("load_workspace should succeed")
+            .expect("workspace should exist");
+        assert_eq!(loaded.id, "ws-1");
+        assert_eq!(loaded.name, "Workspace One");
+        assert_eq!(loaded.description.as_deref(), Some("first"));
+        assert_eq!(loaded.windows_data, "{\"windows\":[]}");
+
+        storage
+            .save_workspace("ws-2", "Workspace Two", None, "{\"windows\":[]}", None)
+            .await
+            .expect("save_workspace should succeed");
+
+        let all = storage
+            .list_workspaces(None)
+            .await
+            .expect("list_workspaces should succeed");
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().any(|w| w.id == "ws-1"));
+        assert!(all.iter().any(|w| w.id == "ws-2"));
+    }
+}
+
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::managers::storage_manager::{DocumentMatch, WorkspaceData};
+
+/// Backend-agnostic surface for everything that needs durable workspace,
+/// document, and settings storage. `StorageManager` (SQLite) is the
+/// production implementation; `InMemoryStore` backs tests and ephemeral
+/// sessions. `AppState` holds this as a trait object so neither commands nor
+/// callers need to know which backend is mounted.
+#[async_trait]
+pub trait WorkspaceStore: Send + Sync {
+    async fn save_workspace(
+        &self,
+        id: &str,
+        name: &str,
+        description: Option<&str>,
+        windows_data: &str,
+        master_key: Option<&[u8; 32]>,
+    ) -> Result<()>;
+
+    async fn load_workspace(&self, id: &str, master_key: Option<&[u8; 32]>) -> Result<Option<WorkspaceData>>;
+
+    async fn list_workspaces(&self, master_key: Option<&[u8; 32]>) -> Result<Vec<WorkspaceData>>;
+
+    /// Re-seal every stored workspace's `windows_data` under `new_key`,
+    /// decrypting with `old_key` first where needed.
+    async fn reencrypt_workspaces(&self, old_key: Option<&[u8; 32]>, new_key: &[u8; 32]) -> Result<()>;
+
+    async fn insert_document(&self, id: &str, title: &str, content: &str, embedding: &[f32]) -> Result<()>;
+
+    async fn search_documents(&self, query: &[f32], k: usize) -> Result<Vec<DocumentMatch>>;
+
+    async fn get_setting(&self, key: &str) -> Result<Option<String>>;
+
+    async fn set_setting(&self, key: &str, value: &str) -> Result<()>;
+}
+
+/// Cosine similarity between two equal-length vectors: `dot(a, b) / (‖a‖·‖b‖)`.
+/// Shared by every `WorkspaceStore` implementation's `search_documents`.
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
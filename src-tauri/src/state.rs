@@ -4,26 +4,72 @@ use anyhow::Result;
 use tauri::AppHandle;
 
 use crate::managers::{
+    in_memory_store::InMemoryStore,
+    job_manager::JobManager,
     storage_manager::StorageManager,
     window_manager::WindowManager,
+    workspace_store::WorkspaceStore,
 };
+use crate::utils::log_buffer::LogBuffer;
+
+/// Number of recent log entries kept in memory for the diagnostics panel.
+pub const LOG_BUFFER_CAPACITY: usize = 1000;
 
 /// Global application state
 pub struct AppState {
     pub app_handle: AppHandle,
-    pub storage_manager: Arc<RwLock<StorageManager>>,
+    pub storage_manager: Arc<RwLock<dyn WorkspaceStore>>,
+    pub db_path: String,
     pub window_manager: Arc<RwLock<WindowManager>>,
+    pub job_manager: Arc<RwLock<JobManager>>,
+    pub log_buffer: LogBuffer,
+    /// The derived master key, once `unlock` has loaded it from the OS
+    /// keyring (or `change_passphrase` has rotated it). `None` means the app
+    /// is locked.
+    pub master_key: Arc<RwLock<Option<[u8; 32]>>>,
 }
 
 impl AppState {
-    pub fn new(app_handle: AppHandle) -> Result<Self> {
-        let storage_manager = Arc::new(RwLock::new(StorageManager::new(&app_handle)?));
+    pub fn new(app_handle: AppHandle, log_buffer: LogBuffer) -> Result<Self> {
+        let storage_manager = StorageManager::new(&app_handle)?;
+        let db_path = storage_manager.db_path().display().to_string();
+        let job_manager = Arc::new(RwLock::new(JobManager::new(storage_manager.pool().clone())));
+        let storage_manager: Arc<RwLock<dyn WorkspaceStore>> = Arc::new(RwLock::new(storage_manager));
+        let window_manager = Arc::new(RwLock::new(WindowManager::new()));
+
+        Ok(Self {
+            app_handle,
+            storage_manager,
+            db_path,
+            window_manager,
+            job_manager,
+            log_buffer,
+            master_key: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    /// Build state backed by an in-memory, non-persistent store. Used for
+    /// integration tests and for "incognito" sessions whose workspaces should
+    /// never survive a restart. `JobManager` still needs a real `SqlitePool`
+    /// (jobs are tracked in a SQLite table by design), so an in-memory SQLite
+    /// database backs just that; workspaces, documents and settings go
+    /// through the HashMap-backed `InMemoryStore` instead, so nothing but job
+    /// bookkeeping touches SQLite in an ephemeral session.
+    pub fn new_in_memory(app_handle: AppHandle) -> Result<Self> {
+        let job_storage = StorageManager::new_in_memory()?;
+        let db_path = job_storage.db_path().display().to_string();
+        let job_manager = Arc::new(RwLock::new(JobManager::new(job_storage.pool().clone())));
+        let storage_manager: Arc<RwLock<dyn WorkspaceStore>> = Arc::new(RwLock::new(InMemoryStore::new()));
         let window_manager = Arc::new(RwLock::new(WindowManager::new()));
 
         Ok(Self {
             app_handle,
             storage_manager,
+            db_path,
             window_manager,
+            job_manager,
+            log_buffer: LogBuffer::new(LOG_BUFFER_CAPACITY),
+            master_key: Arc::new(RwLock::new(None)),
         })
     }
 }
@@ -24,8 +24,40 @@ pub enum AppError {
     Internal(String),
 }
 
+impl AppError {
+    /// A stable, machine-matchable identifier for this variant, independent of
+    /// the human-readable message in `{0}`. Frontend callers that need to
+    /// branch on error kind (e.g. "this was a validation problem, not a crash")
+    /// should match on this rather than parsing `to_string()`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "database",
+            AppError::Io(_) => "io",
+            AppError::Serialization(_) => "serialization",
+            AppError::Encryption(_) => "encryption",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Internal(_) => "internal",
+        }
+    }
+}
+
 impl From<AppError> for String {
     fn from(error: AppError) -> Self {
         error.to_string()
     }
 }
+
+/// Format an `anyhow::Error` for a Tauri command boundary as `"{code}: {msg}"`.
+/// Tauri commands return `Result<T, String>` uniformly across this codebase,
+/// so rather than changing every command's error type, errors that originate
+/// as a typed [`AppError`] keep their stable `code()` prefix through that
+/// `String`, and callers that need to branch on error kind can match on the
+/// prefix instead of guessing from the message text. Errors that aren't an
+/// `AppError` (most `sqlx`/`anyhow::Context` failures) fall back to `"internal"`.
+pub fn command_error(error: anyhow::Error) -> String {
+    match error.downcast::<AppError>() {
+        Ok(app_error) => format!("{}: {}", app_error.code(), app_error),
+        Err(error) => format!("internal: {}", error),
+    }
+}
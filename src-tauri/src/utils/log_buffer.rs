@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use serde::{Deserialize, Serialize};
+use tracing::{Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// A single formatted log event, ready to hand to the frontend diagnostics panel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// A bounded ring buffer of recent log events, shared between the tracing
+/// layer that fills it and the `get_recent_logs` command that reads it.
+#[derive(Clone)]
+pub struct LogBuffer {
+    entries: Arc<RwLock<VecDeque<LogEntry>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    fn push(&self, entry: LogEntry) {
+        let mut entries = self.entries.write().expect("log buffer lock poisoned");
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Most recent entries first, optionally filtered to `min_level` and more
+    /// severe (e.g. `min_level = Level::WARN` returns warnings and errors).
+    pub fn recent(&self, limit: usize, min_level: Option<Level>) -> Vec<LogEntry> {
+        let entries = self.entries.read().expect("log buffer lock poisoned");
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| match min_level {
+                Some(min) => Level::from_str(&entry.level).map_or(true, |level| level <= min),
+                None => true,
+            })
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// A [`tracing_subscriber::Layer`] that mirrors every event into a [`LogBuffer`]
+/// so the desktop UI can poll recent backend activity instead of only seeing
+/// what was written to stdout.
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl LogBufferLayer {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        self.buffer.push(LogEntry {
+            timestamp: chrono::Utc::now().timestamp(),
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+        });
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl tracing::field::Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod errors;
+pub mod log_buffer;
+pub mod logger;